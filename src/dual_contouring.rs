@@ -17,6 +17,223 @@ pub mod material {
   }
 }
 
+/// A feature-preserving vertex solver.
+///
+/// Given the Hermite data for a voxel (the edge-crossing points and their
+/// surface normals), solves for the vertex that minimizes the quadratic
+/// error function `E(x) = sum_i (n_i . (x - p_i))^2`. This keeps sharp
+/// creases and corners intact instead of always falling back to the
+/// average of the crossing points.
+pub mod qef {
+  use cgmath::{Point3, Vector3, EuclideanSpace, InnerSpace};
+
+  /// A single edge crossing: the point where the surface crosses a voxel
+  /// edge, along with the surface normal at that point.
+  pub struct Sample {
+    #[allow(missing_docs)]
+    pub position: Point3<f32>,
+    #[allow(missing_docs)]
+    pub normal: Vector3<f32>,
+  }
+
+  /// Eigenvalues of `A^T A` below this fraction of the largest eigenvalue
+  /// are treated as zero. `A^T A` is rank-deficient in flat or edge
+  /// regions, and without this truncation the solve blows up along the
+  /// corresponding null-space directions.
+  const SINGULAR_VALUE_THRESHOLD: f32 = 0.1;
+
+  /// Solve for the vertex minimizing `E(x) = sum_i (n_i . (x - p_i))^2`,
+  /// clamped to the voxel's bounds `[low, high]`. With no samples there's no
+  /// error function to minimize, so this falls back to the bounds' center.
+  pub fn solve(samples: &[Sample], low: Point3<f32>, high: Point3<f32>) -> Point3<f32> {
+    if samples.is_empty() {
+      return Point3::new(
+        (low.x + high.x) * 0.5,
+        (low.y + high.y) * 0.5,
+        (low.z + high.z) * 0.5,
+      );
+    }
+
+    let mut mass_point = Vector3::new(0.0, 0.0, 0.0);
+    for sample in samples {
+      mass_point += sample.position.to_vec();
+    }
+    mass_point = mass_point / samples.len() as f32;
+
+    // Accumulate the normal equations A^T A x = A^T b directly, without
+    // ever materializing A: A^T A = sum_i n_i n_i^T, A^T b = sum_i n_i (n_i . p_i).
+    let mut ata = [[0.0f32; 3]; 3];
+    let mut atb = Vector3::new(0.0, 0.0, 0.0);
+    for sample in samples {
+      let n = sample.normal;
+      ata[0][0] += n.x * n.x;
+      ata[0][1] += n.x * n.y;
+      ata[0][2] += n.x * n.z;
+      ata[1][1] += n.y * n.y;
+      ata[1][2] += n.y * n.z;
+      ata[2][2] += n.z * n.z;
+      atb += n * n.dot(sample.position.to_vec());
+    }
+    ata[1][0] = ata[0][1];
+    ata[2][0] = ata[0][2];
+    ata[2][1] = ata[1][2];
+
+    let (eigenvalues, eigenvectors) = eigen_decompose(ata);
+    let max_eigenvalue = eigenvalues.iter().cloned().fold(0.0f32, f32::max);
+    let threshold = max_eigenvalue * SINGULAR_VALUE_THRESHOLD;
+
+    // Solve in the space shifted to the mass point: this fills in the
+    // null-space component of the solution with the mass point itself,
+    // since the pseudo-inverse only ever moves `x` along the directions
+    // `A^T A` actually constrains.
+    let residual = atb - apply(&ata, mass_point);
+    let mut offset = Vector3::new(0.0, 0.0, 0.0);
+    for i in 0..3 {
+      if eigenvalues[i] > threshold {
+        offset += eigenvectors[i] * (eigenvectors[i].dot(residual) / eigenvalues[i]);
+      }
+    }
+
+    let vertex = Point3::from_vec(mass_point + offset);
+    Point3::new(
+      vertex.x.max(low.x).min(high.x),
+      vertex.y.max(low.y).min(high.y),
+      vertex.z.max(low.z).min(high.z),
+    )
+  }
+
+  fn apply(m: &[[f32; 3]; 3], v: Vector3<f32>) -> Vector3<f32> {
+    Vector3::new(
+      m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+      m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+      m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+    )
+  }
+
+  /// Diagonalize a symmetric 3x3 matrix with the cyclic Jacobi eigenvalue
+  /// algorithm, returning its eigenvalues and (orthonormal) eigenvectors.
+  /// This is equivalent to an SVD of the original `A`, since the
+  /// eigendecomposition of `A^T A = V D V^T` gives singular values
+  /// `sqrt(D)` and right-singular vectors `V`.
+  fn eigen_decompose(mut a: [[f32; 3]; 3]) -> ([f32; 3], [Vector3<f32>; 3]) {
+    let mut v = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+
+    const SWEEPS: u32 = 8;
+    for _ in 0..SWEEPS {
+      jacobi_rotate(&mut a, &mut v, 0, 1);
+      jacobi_rotate(&mut a, &mut v, 0, 2);
+      jacobi_rotate(&mut a, &mut v, 1, 2);
+    }
+
+    let eigenvalues = [a[0][0], a[1][1], a[2][2]];
+    let eigenvectors = [
+      Vector3::new(v[0][0], v[1][0], v[2][0]),
+      Vector3::new(v[0][1], v[1][1], v[2][1]),
+      Vector3::new(v[0][2], v[1][2], v[2][2]),
+    ];
+    (eigenvalues, eigenvectors)
+  }
+
+  /// Zero out the `(p, q)` off-diagonal pair of symmetric matrix `a` with a
+  /// single Jacobi rotation, accumulating the rotation into `v`.
+  fn jacobi_rotate(a: &mut [[f32; 3]; 3], v: &mut [[f32; 3]; 3], p: usize, q: usize) {
+    if a[p][q].abs() < 1e-10 {
+      return;
+    }
+
+    let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+    let t =
+      if theta >= 0.0 { 1.0 / (theta + (theta * theta + 1.0).sqrt()) }
+      else { 1.0 / (theta - (theta * theta + 1.0).sqrt()) };
+    let c = 1.0 / (t * t + 1.0).sqrt();
+    let s = t * c;
+
+    let app = a[p][p];
+    let aqq = a[q][q];
+    let apq = a[p][q];
+    a[p][p] = c * c * app - 2.0 * s * c * apq + s * s * aqq;
+    a[q][q] = s * s * app + 2.0 * s * c * apq + c * c * aqq;
+    a[p][q] = 0.0;
+    a[q][p] = 0.0;
+
+    for k in 0 .. 3 {
+      if k != p && k != q {
+        let akp = a[k][p];
+        let akq = a[k][q];
+        a[k][p] = c * akp - s * akq;
+        a[p][k] = a[k][p];
+        a[k][q] = s * akp + c * akq;
+        a[q][k] = a[k][q];
+      }
+    }
+
+    for k in 0 .. 3 {
+      let vkp = v[k][p];
+      let vkq = v[k][q];
+      v[k][p] = c * vkp - s * vkq;
+      v[k][q] = s * vkp + c * vkq;
+    }
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use super::*;
+
+    fn assert_close(actual: Point3<f32>, expected: Point3<f32>) {
+      let d = actual - expected;
+      assert!(
+        d.x.abs() < 1e-3 && d.y.abs() < 1e-3 && d.z.abs() < 1e-3,
+        "expected {:?}, got {:?}", expected, actual
+      );
+    }
+
+    fn sample(position: Point3<f32>, normal: Vector3<f32>) -> Sample {
+      Sample { position: position, normal: normal }
+    }
+
+    const LOW: Point3<f32> = Point3 { x: 0.0, y: 0.0, z: 0.0 };
+    const HIGH: Point3<f32> = Point3 { x: 1.0, y: 1.0, z: 1.0 };
+
+    #[test]
+    fn planar_samples_settle_on_the_plane() {
+      // A flat top face at z = 0.5: rank-1 constraint, so only z is
+      // pinned and x/y fall back to the samples' mass point.
+      let samples = vec![
+        sample(Point3::new(0.0, 0.0, 0.5), Vector3::new(0.0, 0.0, 1.0)),
+        sample(Point3::new(1.0, 0.0, 0.5), Vector3::new(0.0, 0.0, 1.0)),
+        sample(Point3::new(0.0, 1.0, 0.5), Vector3::new(0.0, 0.0, 1.0)),
+        sample(Point3::new(1.0, 1.0, 0.5), Vector3::new(0.0, 0.0, 1.0)),
+      ];
+      assert_close(solve(&samples, LOW, HIGH), Point3::new(0.5, 0.5, 0.5));
+    }
+
+    #[test]
+    fn two_perpendicular_planes_settle_on_their_shared_edge() {
+      // An edge crease along x = 0.2, y = 0.3: rank-2 constraint, so x and
+      // y are pinned to the crease and z falls back to the mass point.
+      let samples = vec![
+        sample(Point3::new(0.2, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0)),
+        sample(Point3::new(0.2, 0.0, 1.0), Vector3::new(1.0, 0.0, 0.0)),
+        sample(Point3::new(0.0, 0.3, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+        sample(Point3::new(0.0, 0.3, 1.0), Vector3::new(0.0, 1.0, 0.0)),
+      ];
+      assert_close(solve(&samples, LOW, HIGH), Point3::new(0.2, 0.3, 0.5));
+    }
+
+    #[test]
+    fn three_orthogonal_planes_settle_on_their_shared_corner() {
+      // A full-rank corner at (0.4, 0.6, 0.8): x, y and z are all pinned,
+      // regardless of where the mass point of the samples falls.
+      let samples = vec![
+        sample(Point3::new(0.4, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0)),
+        sample(Point3::new(0.0, 0.6, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+        sample(Point3::new(0.0, 0.0, 0.8), Vector3::new(0.0, 0.0, 1.0)),
+      ];
+      assert_close(solve(&samples, LOW, HIGH), Point3::new(0.4, 0.6, 0.8));
+    }
+  }
+}
+
 #[allow(missing_docs)]
 pub mod polygon {
   use cgmath::{Point3, Vector3};
@@ -29,6 +246,659 @@ pub mod polygon {
     pub normals: [Vector3<f32>; 3],
     #[allow(missing_docs)]
     pub material: Material,
+    /// Per-vertex ambient occlusion factor (0 = fully occluded, 1 = fully
+    /// open), in the same order as `vertices`. `None` unless populated by
+    /// `ambient_occlusion::annotate`.
+    pub occlusion: Option<[f32; 3]>,
+  }
+}
+
+/// An indexed, welded mesh.
+///
+/// `edge::extract` emits fully independent triangles, duplicating every
+/// vertex shared between them. `mesh::T` instead accumulates extraction
+/// output into a deduplicated vertex/normal buffer plus a triangle index
+/// list: vertices coming from the same voxel (matched on
+/// `voxel_data::bounds::T`) map to a single index, so the face-fan quads
+/// produced for 4-neighbor edges share their corner vertices with
+/// neighboring edges instead of duplicating them.
+pub mod mesh {
+  use cgmath::{Point3, Vector3};
+  use std::collections::HashMap;
+  use voxel_data;
+
+  /// An indexed mesh, with a material per triangle.
+  pub struct T<Material> {
+    #[allow(missing_docs)]
+    pub positions: Vec<Point3<f32>>,
+    #[allow(missing_docs)]
+    pub normals: Vec<Vector3<f32>>,
+    #[allow(missing_docs)]
+    pub triangles: Vec<[u32; 3]>,
+    #[allow(missing_docs)]
+    pub materials: Vec<Material>,
+    vertex_bounds: Vec<voxel_data::bounds::T>,
+    // Indexes `vertex_bounds` by exact key, so the common case (a query
+    // matching a previously-seen voxel exactly) is an O(1) lookup instead of
+    // a linear scan; `vertex_bounds` itself is only walked as a fallback, to
+    // catch a query contained in a coarser voxel that already has a vertex.
+    exact_vertex_index: HashMap<voxel_data::bounds::T, u32>,
+  }
+
+  impl<Material> T<Material> {
+    /// Create an empty mesh.
+    pub fn new() -> Self {
+      T {
+        positions: Vec::new(),
+        normals: Vec::new(),
+        triangles: Vec::new(),
+        materials: Vec::new(),
+        vertex_bounds: Vec::new(),
+        exact_vertex_index: HashMap::new(),
+      }
+    }
+
+    /// Append a vertex with no dedup key of its own, e.g. a quad's
+    /// synthesized center vertex.
+    pub fn push_vertex(&mut self, position: Point3<f32>, normal: Vector3<f32>) -> u32 {
+      let index = self.positions.len() as u32;
+      self.positions.push(position);
+      self.normals.push(normal);
+      index
+    }
+
+    /// Append a vertex keyed on the voxel it came from, reusing the
+    /// existing index if this voxel has already produced a vertex.
+    pub fn push_voxel_vertex(
+      &mut self,
+      bounds: voxel_data::bounds::T,
+      position: Point3<f32>,
+      normal: Vector3<f32>,
+    ) -> u32 {
+      if let Some(&index) = self.exact_vertex_index.get(&bounds) {
+        return index;
+      }
+
+      for existing in self.vertex_bounds.iter() {
+        if existing.contains(&bounds) {
+          // `vertex_bounds` skips keyless vertices, so its own position is
+          // not a vertex index; look the coarser voxel's index up instead.
+          let index = self.exact_vertex_index[existing];
+          self.exact_vertex_index.insert(bounds, index);
+          return index;
+        }
+      }
+
+      let index = self.push_vertex(position, normal);
+      self.vertex_bounds.push(bounds);
+      self.exact_vertex_index.insert(bounds, index);
+      index
+    }
+
+    /// Append a triangle referencing three already-pushed vertex indices.
+    pub fn push_triangle(&mut self, indices: [u32; 3], material: Material) {
+      self.triangles.push(indices);
+      self.materials.push(material);
+    }
+  }
+}
+
+/// Per-vertex ambient occlusion, baked during extraction.
+///
+/// For each vertex, `compute` sweeps outward over the opaque voxels in a
+/// hemisphere around the vertex's normal, tracking the angular region
+/// ("shadow") already blocked by voxels encountered closer in; a voxel is
+/// only counted as occluding if its angular slice isn't already covered by
+/// the accumulated shadow. The sweep is a pure function of the vertex's
+/// position, normal, and the surrounding opaque voxels, so it's symmetric
+/// between adjacent vertices: if A occludes B along a ray, B occludes A.
+pub mod ambient_occlusion {
+  use cgmath::{Point3, Vector3, InnerSpace};
+  use voxel_data;
+
+  use super::{material, polygon, voxel_storage};
+
+  /// Tuning for the occlusion sweep.
+  pub struct Parameters {
+    /// How many voxel-widths (at the vertex's `lg_size`) to search
+    /// outward from the vertex.
+    pub radius_in_voxels: i32,
+  }
+
+  impl Default for Parameters {
+    fn default() -> Self {
+      Parameters { radius_in_voxels: 4 }
+    }
+  }
+
+  /// A region of the hemisphere already blocked by a nearer voxel: a cone
+  /// around `direction` with angular radius `angle` (radians).
+  struct Shadow {
+    direction: Vector3<f32>,
+    angle: f32,
+  }
+
+  /// How many directions to rasterize the hemisphere into when tallying how
+  /// much of it the accumulated shadows cover. Evaluating coverage at fixed
+  /// sample directions (rather than summing each cone's solid angle) means
+  /// overlapping cones aren't counted twice.
+  const OCCLUSION_SAMPLES: usize = 64;
+
+  /// A deterministic (Fibonacci spiral) set of directions spread evenly over
+  /// the hemisphere around `normal`.
+  fn hemisphere_samples(normal: Vector3<f32>) -> Vec<Vector3<f32>> {
+    let tangent =
+      if normal.x.abs() < 0.9 {
+        Vector3::new(1.0, 0.0, 0.0).cross(normal).normalize()
+      } else {
+        Vector3::new(0.0, 1.0, 0.0).cross(normal).normalize()
+      };
+    let bitangent = normal.cross(tangent);
+
+    let golden_angle = ::std::f32::consts::PI * (3.0 - (5.0f32).sqrt());
+    let mut samples = Vec::with_capacity(OCCLUSION_SAMPLES);
+    for i in 0 .. OCCLUSION_SAMPLES {
+      let z = 1.0 - (i as f32 + 0.5) / OCCLUSION_SAMPLES as f32;
+      let r = (1.0 - z * z).max(0.0).sqrt();
+      let theta = golden_angle * i as f32;
+      samples.push(tangent * (r * theta.cos()) + bitangent * (r * theta.sin()) + normal * z);
+    }
+    samples
+  }
+
+  /// Compute the ambient occlusion factor (0 = fully occluded, 1 = fully
+  /// open) at `vertex`, whose surface normal is `normal`, by sweeping
+  /// outward over the opaque voxels of `voxels` within
+  /// `parameters.radius_in_voxels` of the voxel grid at `lg_size`.
+  pub fn compute<Material, Voxels>(
+    voxels: &mut Voxels,
+    vertex: Point3<f32>,
+    normal: Vector3<f32>,
+    lg_size: i16,
+    parameters: &Parameters,
+  ) -> f32 where
+    Material: material::T,
+    Voxels: voxel_storage::T<Material>,
+  {
+    // A degenerate normal (e.g. the `Vector3::zero()` fallback left by
+    // unresolved Hermite data) has no hemisphere to sweep; normalizing it
+    // would produce NaNs that `acos() <= angle` silently treats as
+    // unoccluded, so report fully open instead of faking a direction.
+    if normal.magnitude2() <= 1e-12 {
+      return 1.0;
+    }
+    let normal = normal.normalize();
+    let size = (2.0f32).powi(lg_size as i32);
+    let center_index = Point3::new(
+      (vertex.x / size).floor() as i32,
+      (vertex.y / size).floor() as i32,
+      (vertex.z / size).floor() as i32,
+    );
+
+    // Gather the opaque voxels in the hemisphere, nearest first, so
+    // closer voxels get to claim their shadow before farther ones are
+    // checked against it.
+    let r = parameters.radius_in_voxels;
+    let mut candidates: Vec<(f32, Vector3<f32>, f32)> = Vec::new();
+    for dz in -r .. r + 1 {
+      for dy in -r .. r + 1 {
+        for dx in -r .. r + 1 {
+          if dx == 0 && dy == 0 && dz == 0 {
+            continue;
+          }
+
+          let index = Point3::new(center_index.x + dx, center_index.y + dy, center_index.z + dz);
+          let bounds = voxel_data::bounds::new(index.x, index.y, index.z, lg_size);
+          let opaque =
+            match voxels.get_material(&bounds) {
+              None => continue,
+              Some(material) => material.is_opaque(),
+            };
+          if !opaque {
+            continue;
+          }
+
+          let voxel_center =
+            Point3::new(
+              (index.x as f32 + 0.5) * size,
+              (index.y as f32 + 0.5) * size,
+              (index.z as f32 + 0.5) * size,
+            );
+          let to_voxel = voxel_center - vertex;
+          let distance = to_voxel.magnitude();
+          if distance < 1e-6 {
+            continue;
+          }
+
+          let direction = to_voxel / distance;
+          if direction.dot(normal) <= 0.0 {
+            // Behind the surface; not in the hemisphere this vertex faces.
+            continue;
+          }
+
+          // The voxel's apparent angular radius as seen from `vertex`,
+          // approximating it as a sphere inscribed in its cell.
+          let apparent_radius = (size * 0.5 / distance).min(1.0).asin();
+          candidates.push((distance, direction, apparent_radius));
+        }
+      }
+    }
+    candidates.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+    let mut shadows: Vec<Shadow> = Vec::new();
+
+    for (_, direction, apparent_radius) in candidates {
+      let already_covered =
+        shadows.iter().any(|shadow| {
+          let separation = shadow.direction.dot(direction).max(-1.0).min(1.0).acos();
+          separation + apparent_radius <= shadow.angle
+        });
+      if already_covered {
+        continue;
+      }
+
+      shadows.push(Shadow { direction: direction, angle: apparent_radius });
+    }
+
+    // Tally the fraction of the hemisphere covered by the union of the
+    // accumulated shadow cones. Summing each cone's solid angle directly
+    // would double-count the overlap between cones that partially (but not
+    // fully) cover one another, so instead check coverage at a fixed set of
+    // sample directions and count each one at most once.
+    let samples = hemisphere_samples(normal);
+    let blocked =
+      samples.iter()
+        .filter(|&&sample| {
+          shadows.iter().any(|shadow| {
+            shadow.direction.dot(sample).max(-1.0).min(1.0).acos() <= shadow.angle
+          })
+        })
+        .count();
+
+    (1.0 - blocked as f32 / samples.len() as f32).max(0.0)
+  }
+
+  /// Populate `polygon.occlusion` by running `compute` for each of its
+  /// three vertices. `extract`/`extract_mesh`/`extract_lod` always leave
+  /// `occlusion` as `None`; callers that want the pass run it explicitly.
+  pub fn annotate<Material, Voxels>(
+    voxels: &mut Voxels,
+    lg_size: i16,
+    parameters: &Parameters,
+    polygon: &mut polygon::T<Material>,
+  ) where
+    Material: material::T,
+    Voxels: voxel_storage::T<Material>,
+  {
+    let mut occlusion = [0.0f32; 3];
+    for i in 0 .. 3 {
+      occlusion[i] = compute(voxels, polygon.vertices[i], polygon.normals[i], lg_size, parameters);
+    }
+    polygon.occlusion = Some(occlusion);
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use super::*;
+
+    #[derive(Clone, PartialEq, Eq)]
+    struct TestMaterial(bool);
+
+    impl material::T for TestMaterial {
+      fn is_opaque(&self) -> bool { self.0 }
+    }
+
+    struct SingleOpaqueVoxel {
+      opaque: voxel_data::bounds::T,
+    }
+
+    impl voxel_storage::T<TestMaterial> for SingleOpaqueVoxel {
+      fn get_material(&mut self, voxel: &voxel_data::bounds::T) -> Option<TestMaterial> {
+        Some(TestMaterial(*voxel == self.opaque))
+      }
+
+      fn get_voxel_data(&mut self, _voxel: &voxel_data::bounds::T) -> Option<voxel_storage::VoxelData> {
+        None
+      }
+    }
+
+    #[test]
+    fn a_single_adjacent_opaque_voxel_occludes_its_known_solid_angle() {
+      // One opaque voxel, one cell straight ahead of the normal: its
+      // inscribed-sphere apparent radius is asin(0.5) =~ 30 degrees, which
+      // blocks exactly 9 of the 64 fixed hemisphere samples around the
+      // normal.
+      let mut voxels = SingleOpaqueVoxel { opaque: voxel_data::bounds::new(0, 0, 1, 0) };
+      let vertex = Point3::new(0.5, 0.5, 0.5);
+      let normal = Vector3::new(0.0, 0.0, 1.0);
+      let parameters = Parameters { radius_in_voxels: 1 };
+
+      let occlusion = compute(&mut voxels, vertex, normal, 0, &parameters);
+
+      assert!((occlusion - 55.0 / 64.0).abs() < 1e-5, "expected 55/64, got {}", occlusion);
+    }
+
+    #[test]
+    fn no_opaque_neighbors_is_fully_open() {
+      let mut voxels = SingleOpaqueVoxel { opaque: voxel_data::bounds::new(99, 99, 99, 0) };
+      let vertex = Point3::new(0.5, 0.5, 0.5);
+      let normal = Vector3::new(0.0, 0.0, 1.0);
+      let parameters = Parameters { radius_in_voxels: 1 };
+
+      assert_eq!(compute(&mut voxels, vertex, normal, 0, &parameters), 1.0);
+    }
+  }
+}
+
+/// Export an indexed `mesh::T` to standard interchange formats.
+///
+/// `material::T` only requires `Eq + is_opaque`, so it carries no name or
+/// appearance data of its own; callers supply a `name_of` mapper to turn
+/// each material into the string OBJ groups/PLY comments use.
+pub mod export {
+  use std::io;
+  use std::io::Write;
+
+  use super::mesh;
+
+  /// Write `mesh` as a Wavefront OBJ: `v`/`vn` for the (parallel)
+  /// position/normal buffers, and `f` faces grouped by material under a
+  /// `g` line per distinct material name. If `mtllib` is given, it's
+  /// emitted as a reference to an external material library with that
+  /// filename (see `write_mtl`), and each `g` line is followed by a
+  /// matching `usemtl` line; without it, no `usemtl` lines are written.
+  pub fn write_obj<Material, W, NameOf>(
+    mesh: &mesh::T<Material>,
+    name_of: NameOf,
+    mtllib: Option<&str>,
+    out: &mut W,
+  ) -> io::Result<()> where
+    W: Write,
+    NameOf: Fn(&Material) -> String,
+  {
+    if let Some(filename) = mtllib {
+      try!(writeln!(out, "mtllib {}", filename));
+    }
+
+    for position in &mesh.positions {
+      try!(writeln!(out, "v {} {} {}", position.x, position.y, position.z));
+    }
+    for normal in &mesh.normals {
+      try!(writeln!(out, "vn {} {} {}", normal.x, normal.y, normal.z));
+    }
+
+    // Bucket faces by material name, preserving the order each name was
+    // first seen in, so each material gets one contiguous `g` section.
+    let mut groups: Vec<(String, Vec<[u32; 3]>)> = Vec::new();
+    for (triangle, material) in mesh.triangles.iter().zip(&mesh.materials) {
+      let name = name_of(material);
+      match groups.iter_mut().find(|group| group.0 == name) {
+        Some(group) => group.1.push(*triangle),
+        None => groups.push((name, vec![*triangle])),
+      }
+    }
+
+    for (name, faces) in groups {
+      try!(writeln!(out, "g {}", name));
+      if mtllib.is_some() {
+        try!(writeln!(out, "usemtl {}", name));
+      }
+      for face in faces {
+        // OBJ indices are 1-based `position/texcoord/normal` triples; this
+        // mesh has no texture coordinates, so that slot is left empty.
+        // Position and normal share an index, since `mesh::T` keeps them
+        // in parallel per-vertex buffers.
+        try!(writeln!(
+          out,
+          "f {0}//{0} {1}//{1} {2}//{2}",
+          face[0] + 1, face[1] + 1, face[2] + 1,
+        ));
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Write a minimal `.mtl` material library: one `newmtl <name>` per
+  /// distinct material name in `mesh`, matching the names `write_obj`'s
+  /// `g`/`usemtl` lines use.
+  pub fn write_mtl<Material, W, NameOf>(
+    mesh: &mesh::T<Material>,
+    name_of: NameOf,
+    out: &mut W,
+  ) -> io::Result<()> where
+    W: Write,
+    NameOf: Fn(&Material) -> String,
+  {
+    let mut seen: Vec<String> = Vec::new();
+    for material in &mesh.materials {
+      let name = name_of(material);
+      if !seen.iter().any(|existing| *existing == name) {
+        try!(writeln!(out, "newmtl {}", name));
+        seen.push(name);
+      }
+    }
+    Ok(())
+  }
+
+  /// PLY encoding to write with `write_ply`.
+  pub enum PlyFormat {
+    #[allow(missing_docs)]
+    Ascii,
+    #[allow(missing_docs)]
+    BinaryLittleEndian,
+  }
+
+  /// Write `mesh` as a PLY file: a `vertex` element (position + normal)
+  /// and a `face` element (the triangle's vertex indices plus a
+  /// `material_index` into the distinct material names listed in the
+  /// header comments, since PLY has no native named-group concept like
+  /// OBJ).
+  pub fn write_ply<Material, W, NameOf>(
+    mesh: &mesh::T<Material>,
+    name_of: NameOf,
+    format: PlyFormat,
+    out: &mut W,
+  ) -> io::Result<()> where
+    W: Write,
+    NameOf: Fn(&Material) -> String,
+  {
+    let mut names: Vec<String> = Vec::new();
+    let mut material_indices: Vec<u32> = Vec::with_capacity(mesh.materials.len());
+    for material in &mesh.materials {
+      let name = name_of(material);
+      let index =
+        match names.iter().position(|existing| *existing == name) {
+          Some(index) => index,
+          None => { names.push(name); names.len() - 1 },
+        };
+      material_indices.push(index as u32);
+    }
+
+    try!(writeln!(out, "ply"));
+    match format {
+      PlyFormat::Ascii => try!(writeln!(out, "format ascii 1.0")),
+      PlyFormat::BinaryLittleEndian => try!(writeln!(out, "format binary_little_endian 1.0")),
+    }
+    for (index, name) in names.iter().enumerate() {
+      try!(writeln!(out, "comment material {} {}", index, name));
+    }
+    try!(writeln!(out, "element vertex {}", mesh.positions.len()));
+    try!(writeln!(out, "property float x"));
+    try!(writeln!(out, "property float y"));
+    try!(writeln!(out, "property float z"));
+    try!(writeln!(out, "property float nx"));
+    try!(writeln!(out, "property float ny"));
+    try!(writeln!(out, "property float nz"));
+    try!(writeln!(out, "element face {}", mesh.triangles.len()));
+    try!(writeln!(out, "property list uchar int vertex_indices"));
+    try!(writeln!(out, "property int material_index"));
+    try!(writeln!(out, "end_header"));
+
+    match format {
+      PlyFormat::Ascii => {
+        for (position, normal) in mesh.positions.iter().zip(&mesh.normals) {
+          try!(writeln!(
+            out,
+            "{} {} {} {} {} {}",
+            position.x, position.y, position.z, normal.x, normal.y, normal.z,
+          ));
+        }
+        for (triangle, material_index) in mesh.triangles.iter().zip(&material_indices) {
+          try!(writeln!(out, "3 {} {} {} {}", triangle[0], triangle[1], triangle[2], material_index));
+        }
+      },
+      PlyFormat::BinaryLittleEndian => {
+        for (position, normal) in mesh.positions.iter().zip(&mesh.normals) {
+          try!(write_f32_le(out, position.x));
+          try!(write_f32_le(out, position.y));
+          try!(write_f32_le(out, position.z));
+          try!(write_f32_le(out, normal.x));
+          try!(write_f32_le(out, normal.y));
+          try!(write_f32_le(out, normal.z));
+        }
+        for (triangle, material_index) in mesh.triangles.iter().zip(&material_indices) {
+          try!(out.write_all(&[3u8]));
+          try!(write_u32_le(out, triangle[0]));
+          try!(write_u32_le(out, triangle[1]));
+          try!(write_u32_le(out, triangle[2]));
+          try!(write_u32_le(out, *material_index));
+        }
+      },
+    }
+
+    Ok(())
+  }
+
+  fn write_u32_le<W: Write>(out: &mut W, v: u32) -> io::Result<()> {
+    let bytes = [
+      (v & 0xff) as u8,
+      ((v >> 8) & 0xff) as u8,
+      ((v >> 16) & 0xff) as u8,
+      ((v >> 24) & 0xff) as u8,
+    ];
+    out.write_all(&bytes)
+  }
+
+  fn write_f32_le<W: Write>(out: &mut W, v: f32) -> io::Result<()> {
+    write_u32_le(out, v.to_bits())
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use super::*;
+    use cgmath::{Point3, Vector3};
+
+    fn two_triangle_mesh() -> mesh::T<i32> {
+      let mut mesh = mesh::T::new();
+      mesh.push_vertex(Point3::new(0.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+      mesh.push_vertex(Point3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0));
+      mesh.push_vertex(Point3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+      mesh.push_vertex(Point3::new(0.0, 0.0, 1.0), Vector3::new(1.0, 1.0, 1.0));
+      mesh.push_triangle([0, 1, 2], 0);
+      mesh.push_triangle([1, 2, 3], 1);
+      mesh
+    }
+
+    fn name_of(material: &i32) -> String {
+      if *material == 0 { "stone".to_string() } else { "dirt".to_string() }
+    }
+
+    #[test]
+    fn write_obj_groups_faces_by_material() {
+      let mesh = two_triangle_mesh();
+      let mut out = Vec::new();
+      write_obj(&mesh, name_of, Some("mesh.mtl"), &mut out).unwrap();
+      let text = String::from_utf8(out).unwrap();
+
+      assert_eq!(
+        text,
+        "mtllib mesh.mtl\n\
+         v 0 0 0\n\
+         v 1 0 0\n\
+         v 0 1 0\n\
+         v 0 0 1\n\
+         vn 1 0 0\n\
+         vn 0 1 0\n\
+         vn 0 0 1\n\
+         vn 1 1 1\n\
+         g stone\n\
+         usemtl stone\n\
+         f 1//1 2//2 3//3\n\
+         g dirt\n\
+         usemtl dirt\n\
+         f 2//2 3//3 4//4\n",
+      );
+    }
+
+    #[test]
+    fn write_mtl_lists_each_distinct_material_once() {
+      let mesh = two_triangle_mesh();
+      let mut out = Vec::new();
+      write_mtl(&mesh, name_of, &mut out).unwrap();
+      let text = String::from_utf8(out).unwrap();
+
+      assert_eq!(text, "newmtl stone\nnewmtl dirt\n");
+    }
+
+    #[test]
+    fn write_ply_ascii_includes_header_and_material_index() {
+      let mesh = two_triangle_mesh();
+      let mut out = Vec::new();
+      write_ply(&mesh, name_of, PlyFormat::Ascii, &mut out).unwrap();
+      let text = String::from_utf8(out).unwrap();
+
+      assert_eq!(
+        text,
+        "ply\n\
+         format ascii 1.0\n\
+         comment material 0 stone\n\
+         comment material 1 dirt\n\
+         element vertex 4\n\
+         property float x\n\
+         property float y\n\
+         property float z\n\
+         property float nx\n\
+         property float ny\n\
+         property float nz\n\
+         element face 2\n\
+         property list uchar int vertex_indices\n\
+         property int material_index\n\
+         end_header\n\
+         0 0 0 1 0 0\n\
+         1 0 0 0 1 0\n\
+         0 1 0 0 0 1\n\
+         0 0 1 1 1 1\n\
+         3 0 1 2 0\n\
+         3 1 2 3 1\n",
+      );
+    }
+
+    #[test]
+    fn write_ply_binary_encodes_vertices_and_faces_little_endian() {
+      let mesh = two_triangle_mesh();
+      let mut out = Vec::new();
+      write_ply(&mesh, name_of, PlyFormat::BinaryLittleEndian, &mut out).unwrap();
+
+      let header_end = out.windows(11).position(|w| w == b"end_header\n").unwrap() + 11;
+      assert!(String::from_utf8_lossy(&out[.. header_end]).contains("format binary_little_endian 1.0\n"));
+
+      let body = &out[header_end ..];
+      // 4 vertices * 6 f32s + 2 faces * (1 count byte + 3 u32 indices + 1 u32 material index).
+      assert_eq!(body.len(), 4 * 6 * 4 + 2 * (1 + 4 * 4));
+
+      // First vertex: position (0, 0, 0), normal (1, 0, 0).
+      assert_eq!(&body[0 .. 4], &0.0f32.to_bits().to_le_bytes());
+      assert_eq!(&body[12 .. 16], &1.0f32.to_bits().to_le_bytes());
+
+      // First face: 3 indices (0, 1, 2), material index 0.
+      let faces = &body[4 * 6 * 4 ..];
+      assert_eq!(faces[0], 3u8);
+      assert_eq!(&faces[1 .. 5], &0u32.to_le_bytes());
+      assert_eq!(&faces[5 .. 9], &1u32.to_le_bytes());
+      assert_eq!(&faces[9 .. 13], &2u32.to_le_bytes());
+      assert_eq!(&faces[13 .. 17], &0u32.to_le_bytes());
+    }
   }
 }
 
@@ -37,7 +907,7 @@ pub mod voxel_storage {
   use cgmath::{Point3, Vector3};
   use voxel_data;
 
-  use super::material;
+  use super::{material, qef};
 
   #[allow(missing_docs)]
   pub struct VoxelData {
@@ -46,21 +916,40 @@ pub mod voxel_storage {
     pub normal: Vector3<f32>,
   }
 
+  /// Hermite data for a voxel: the edge crossings where the surface meets
+  /// the voxel, paired with this voxel's world-space bounds, for use with
+  /// `qef::solve`.
+  #[allow(missing_docs)]
+  pub struct HermiteData {
+    pub samples: Vec<qef::Sample>,
+    pub low: Point3<f32>,
+    pub high: Point3<f32>,
+  }
+
   /// The voxel storage interface required by dual contouring.
   pub trait T<Material> where Material: material::T {
     #[allow(missing_docs)]
     fn get_material(&mut self, voxel: &voxel_data::bounds::T) -> Option<Material>;
     /// Get the data for the given voxel. This function may also return data from a larger encompassing voxel.
     fn get_voxel_data(&mut self, voxel: &voxel_data::bounds::T) -> Option<VoxelData>;
+
+    /// Optionally provide this voxel's Hermite data, to solve for a
+    /// feature-preserving vertex with `qef::solve` instead of using the
+    /// precomputed `vertex`/`normal` from `get_voxel_data`. Voxel storages
+    /// that don't implement this are unaffected and keep using
+    /// `get_voxel_data` as before.
+    fn get_hermite_data(&mut self, _voxel: &voxel_data::bounds::T) -> Option<HermiteData> {
+      None
+    }
   }
 }
 
 #[allow(missing_docs)]
 pub mod edge {
-  use cgmath::{Point3, Vector3, EuclideanSpace};
+  use cgmath::{Point3, Vector3, EuclideanSpace, InnerSpace, Zero};
   use voxel_data;
 
-  use super::{voxel_storage, polygon, material};
+  use super::{voxel_storage, polygon, material, qef, mesh};
 
   #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
   pub enum Direction { X, Y, Z }
@@ -104,6 +993,42 @@ pub mod edge {
     ]
   }
 
+  /// Like `neighbors`, but let each of the four neighbors be resolved at
+  /// its own (possibly coarser) `lg_size` instead of assuming they all
+  /// share `edge.lg_size`. `neighbor_lg_size[i] >= edge.lg_size`; the
+  /// caller supplies it based on its own octree, since only it knows
+  /// which neighbors actually sit in a coarser region.
+  fn neighbors_at_lod(edge: &T, neighbor_lg_size: [i16; 4]) -> [voxel_data::bounds::T; 4] {
+    for lg_size in &neighbor_lg_size {
+      debug_assert!(*lg_size >= edge.lg_size);
+    }
+    let (v1, v2) =
+      match edge.direction {
+        Direction::X => (Vector3::new(0, 0, -1), Vector3::new(0, -1, 0)),
+        Direction::Y => (Vector3::new(-1, 0, 0), Vector3::new(0, 0, -1)),
+        Direction::Z => (Vector3::new(0, -1, 0), Vector3::new(-1, 0, 0)),
+      };
+    let corners = [
+      edge.low_corner,
+      edge.low_corner + v1,
+      edge.low_corner + v1 + v2,
+      edge.low_corner + v2,
+    ];
+    // Re-express `p` (a grid coordinate at `edge.lg_size`) as a grid
+    // coordinate at the coarser `lg_size`, by floor-dividing out the size
+    // difference between the two levels.
+    let make_bounds = |p: Point3<i32>, lg_size: i16| {
+      let shift = lg_size - edge.lg_size;
+      voxel_data::bounds::new(p.x >> shift, p.y >> shift, p.z >> shift, lg_size)
+    };
+    [
+      make_bounds(corners[0], neighbor_lg_size[0]),
+      make_bounds(corners[1], neighbor_lg_size[1]),
+      make_bounds(corners[2], neighbor_lg_size[2]),
+      make_bounds(corners[3], neighbor_lg_size[3]),
+    ]
+  }
+
   enum Crossing<Material> {
     Undefined,
     None,
@@ -144,7 +1069,7 @@ pub mod edge {
   fn resolve_voxels<'a, Material, Voxels, It>(
     voxels: &mut Voxels,
     bounds: It,
-  ) -> Result<Vec<(Point3<f32>, Vector3<f32>)>, ()> where
+  ) -> Result<Vec<(voxel_data::bounds::T, Point3<f32>, Vector3<f32>)>, ()> where
     Material: material::T,
     Voxels: voxel_storage::T<Material>,
     It: Iterator<Item=&'a voxel_data::bounds::T>,
@@ -159,13 +1084,29 @@ pub mod edge {
         }
       }
 
+      if let Some(hermite) = voxels.get_hermite_data(bounds) {
+        let vertex = qef::solve(&hermite.samples, hermite.low, hermite.high);
+        let mut normal = Vector3::zero();
+        for sample in &hermite.samples {
+          normal += sample.normal;
+        }
+        // An empty sample list, or samples whose normals cancel out (e.g.
+        // symmetric crossings), leave `normal` at or near zero;
+        // `normalize()` on that would produce NaNs, so fall back to a zero
+        // normal instead of a meaningless unit vector.
+        let normal = if normal.magnitude2() > 1e-12 { normal.normalize() } else { Vector3::zero() };
+        resolved_bounds.push(*bounds);
+        resolved_voxel_data.push((*bounds, vertex, normal));
+        continue 'resolve_loop;
+      }
+
       let voxel_data =
         match voxels.get_voxel_data(bounds) {
           None => return Err(()),
           Some(d) => d,
         };
       resolved_bounds.push(voxel_data.bounds);
-      resolved_voxel_data.push((voxel_data.vertex, voxel_data.normal));
+      resolved_voxel_data.push((voxel_data.bounds, voxel_data.vertex, voxel_data.normal));
     }
 
     Ok(resolved_voxel_data)
@@ -195,21 +1136,22 @@ pub mod edge {
       };
 
     if vertices_and_normals.len() == 3 {
-      let (v0, n0) = vertices_and_normals[0];
-      let (v1, n1) = vertices_and_normals[1];
-      let (v2, n2) = vertices_and_normals[2];
+      let (_, v0, n0) = vertices_and_normals[0];
+      let (_, v1, n1) = vertices_and_normals[1];
+      let (_, v2, n2) = vertices_and_normals[2];
       poly(
         polygon::T {
           vertices: [v0, v1, v2],
           normals: [n0, n1, n2],
           material: material,
+          occlusion: None,
         }
       );
     } else if vertices_and_normals.len() == 4 {
-      let (v0, n0) = vertices_and_normals[0];
-      let (v1, n1) = vertices_and_normals[1];
-      let (v2, n2) = vertices_and_normals[2];
-      let (v3, n3) = vertices_and_normals[3];
+      let (_, v0, n0) = vertices_and_normals[0];
+      let (_, v1, n1) = vertices_and_normals[1];
+      let (_, v2, n2) = vertices_and_normals[2];
+      let (_, v3, n3) = vertices_and_normals[3];
       let v_center =
         (v0 + v1.to_vec() + v2.to_vec() + v3.to_vec()) / 4.0;
       let n_center =
@@ -219,6 +1161,7 @@ pub mod edge {
           vertices: [v0, v1, v_center],
           normals: [n0, n1, n_center],
           material: material.clone(),
+          occlusion: None,
         }
       );
       poly(
@@ -226,6 +1169,7 @@ pub mod edge {
           vertices: [v1, v2, v_center],
           normals: [n1, n2, n_center],
           material: material.clone(),
+          occlusion: None,
         }
       );
       poly(
@@ -233,6 +1177,7 @@ pub mod edge {
           vertices: [v2, v3, v_center],
           normals: [n2, n3, n_center],
           material: material.clone(),
+          occlusion: None,
         }
       );
       poly(
@@ -240,6 +1185,7 @@ pub mod edge {
           vertices: [v3, v0, v_center],
           normals: [n3, n0, n_center],
           material: material.clone(),
+          occlusion: None,
         }
       );
     } else {
@@ -248,4 +1194,792 @@ pub mod edge {
 
     Ok(())
   }
+
+  /// Run dual contouring on a single edge, like `extract`, but accumulate
+  /// the result into an indexed `mesh::T` instead of emitting independent
+  /// triangles. Vertices are welded across edges that share a voxel.
+  pub fn extract_mesh<Material, Voxels>(
+    voxels: &mut Voxels,
+    edge: &T,
+    mesh: &mut mesh::T<Material>,
+  ) -> Result<(), ()> where
+    Material: material::T + Clone,
+    Voxels: voxel_storage::T<Material>,
+  {
+    let (material, vertices_and_normals) =
+      match crossing(voxels, edge) {
+        Crossing::Undefined => return Err(()),
+        Crossing::None => return Ok(()),
+        Crossing::HighInside(material) => {
+          (material, try!(resolve_voxels(voxels, neighbors(&edge).iter())))
+        },
+        Crossing::LowInside(material) => {
+          (material, try!(resolve_voxels(voxels, neighbors(&edge).iter().rev())))
+        }
+      };
+
+    if vertices_and_normals.len() == 3 {
+      let (b0, v0, n0) = vertices_and_normals[0];
+      let (b1, v1, n1) = vertices_and_normals[1];
+      let (b2, v2, n2) = vertices_and_normals[2];
+      let i0 = mesh.push_voxel_vertex(b0, v0, n0);
+      let i1 = mesh.push_voxel_vertex(b1, v1, n1);
+      let i2 = mesh.push_voxel_vertex(b2, v2, n2);
+      mesh.push_triangle([i0, i1, i2], material);
+    } else if vertices_and_normals.len() == 4 {
+      let (b0, v0, n0) = vertices_and_normals[0];
+      let (b1, v1, n1) = vertices_and_normals[1];
+      let (b2, v2, n2) = vertices_and_normals[2];
+      let (b3, v3, n3) = vertices_and_normals[3];
+      let v_center =
+        (v0 + v1.to_vec() + v2.to_vec() + v3.to_vec()) / 4.0;
+      let n_center =
+        (n0 + n1 + n2 + n3) / 4.0;
+
+      let i0 = mesh.push_voxel_vertex(b0, v0, n0);
+      let i1 = mesh.push_voxel_vertex(b1, v1, n1);
+      let i2 = mesh.push_voxel_vertex(b2, v2, n2);
+      let i3 = mesh.push_voxel_vertex(b3, v3, n3);
+      let i_center = mesh.push_vertex(v_center, n_center);
+
+      mesh.push_triangle([i0, i1, i_center], material.clone());
+      mesh.push_triangle([i1, i2, i_center], material.clone());
+      mesh.push_triangle([i2, i3, i_center], material.clone());
+      mesh.push_triangle([i3, i0, i_center], material.clone());
+    } else {
+      panic!("Edge has an unexpected number of neighbors: {}", vertices_and_normals.len());
+    }
+
+    Ok(())
+  }
+
+  /// Run dual contouring on a single edge, like `extract`, but let each of
+  /// the edge's four neighbor voxels be resolved at its own (possibly
+  /// coarser) `lg_size` via `neighbor_lg_size`, instead of assuming they
+  /// all share `edge.lg_size`. This is the mode to use at an LOD seam
+  /// (e.g. an octree cut): when one or more neighbors are coarser, several
+  /// of the edge's logical corners resolve to the same larger neighbor
+  /// voxel (`resolve_voxels` already dedups this). If only 1 distinct
+  /// voxel remains, nothing is emitted; if 3 remain, they form a single
+  /// triangle; if 2 or 4 remain, all 4 logical corners (repeating a shared
+  /// voxel's vertex where corners collapse) are fanned around their
+  /// centroid, so the mesh stays watertight across the seam instead of
+  /// T-junction cracking.
+  pub fn extract_lod<Material, Voxels, OnPolygon>(
+    voxels: &mut Voxels,
+    edge: &T,
+    neighbor_lg_size: [i16; 4],
+    mut poly: OnPolygon,
+  ) -> Result<(), ()> where
+    Material: material::T + Clone,
+    Voxels: voxel_storage::T<Material>,
+    OnPolygon: FnMut(polygon::T<Material>),
+  {
+    let (material, bounds_order, vertices_and_normals) =
+      match crossing(voxels, edge) {
+        Crossing::Undefined => return Err(()),
+        Crossing::None => return Ok(()),
+        Crossing::HighInside(material) => {
+          let bounds = neighbors_at_lod(edge, neighbor_lg_size);
+          let resolved = try!(resolve_voxels(voxels, bounds.iter()));
+          (material, bounds, resolved)
+        },
+        Crossing::LowInside(material) => {
+          let mut bounds = neighbors_at_lod(edge, neighbor_lg_size);
+          bounds.reverse();
+          let resolved = try!(resolve_voxels(voxels, bounds.iter()));
+          (material, bounds, resolved)
+        }
+      };
+
+    let corners = vertices_and_normals.len();
+    if corners == 1 {
+      // The whole quad collapsed onto a single voxel: this edge is
+      // entirely interior to a single coarser voxel, and the coarse-side
+      // mesh already covers this patch of the seam.
+      return Ok(());
+    }
+
+    if corners == 3 {
+      let (_, v0, n0) = vertices_and_normals[0];
+      let (_, v1, n1) = vertices_and_normals[1];
+      let (_, v2, n2) = vertices_and_normals[2];
+      poly(
+        polygon::T {
+          vertices: [v0, v1, v2],
+          normals: [n0, n1, n2],
+          material: material,
+          occlusion: None,
+        }
+      );
+      return Ok(());
+    }
+
+    // `corners` is 2 or 4. Rather than fan only the distinct resolved
+    // voxels, look each of the 4 logical quad corners back up in
+    // `vertices_and_normals` (two or more corners may share the same
+    // resolved voxel) and fan all 4, in order, around their centroid.
+    // When 2 distinct voxels straddle the quad (e.g. a transition line
+    // bisecting it, or an L-shaped 1-vs-3 split) neither voxel's own
+    // extraction pass covers this interior seam, so this is the only
+    // place that closes it; the repeated-corner legs of the fan are
+    // simply zero-area and harmless.
+    let corner = |i: usize| -> (Point3<f32>, Vector3<f32>) {
+      for &(bounds, v, n) in &vertices_and_normals {
+        if bounds.contains(&bounds_order[i]) {
+          return (v, n);
+        }
+      }
+      panic!("resolve_voxels did not account for logical corner {}", i);
+    };
+
+    let mut center = Vector3::zero();
+    let mut center_normal = Vector3::zero();
+    for i in 0 .. 4 {
+      let (v, n) = corner(i);
+      center += v.to_vec();
+      center_normal += n;
+    }
+    center = center / 4.0;
+    let center = Point3::from_vec(center);
+    let center_normal =
+      if center_normal.magnitude2() > 1e-12 { center_normal.normalize() } else { Vector3::zero() };
+
+    for i in 0 .. 4 {
+      let (v0, n0) = corner(i);
+      let (v1, n1) = corner((i + 1) % 4);
+      poly(
+        polygon::T {
+          vertices: [v0, v1, center],
+          normals: [n0, n1, center_normal],
+          material: material.clone(),
+          occlusion: None,
+        }
+      );
+    }
+
+    Ok(())
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use super::*;
+
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    struct TestMaterial(bool);
+
+    impl material::T for TestMaterial {
+      fn is_opaque(&self) -> bool { self.0 }
+    }
+
+    /// A voxel storage backed by an explicit list of bounds -> data, so a
+    /// test can hand-pick exactly which `neighbors_at_lod` entries collapse
+    /// onto a shared coarser voxel and which stay distinct.
+    struct Fixture {
+      entries: Vec<(voxel_data::bounds::T, TestMaterial, Point3<f32>, Vector3<f32>)>,
+    }
+
+    impl Fixture {
+      fn new() -> Self {
+        Fixture { entries: Vec::new() }
+      }
+
+      fn put(&mut self, bounds: voxel_data::bounds::T, opaque: bool, vertex: Point3<f32>, normal: Vector3<f32>) {
+        self.entries.push((bounds, TestMaterial(opaque), vertex, normal));
+      }
+    }
+
+    impl voxel_storage::T<TestMaterial> for Fixture {
+      fn get_material(&mut self, voxel: &voxel_data::bounds::T) -> Option<TestMaterial> {
+        self.entries.iter().find(|entry| entry.0 == *voxel).map(|entry| entry.1.clone())
+      }
+
+      fn get_voxel_data(&mut self, voxel: &voxel_data::bounds::T) -> Option<voxel_storage::VoxelData> {
+        self.entries.iter().find(|entry| entry.0 == *voxel)
+          .map(|entry| voxel_storage::VoxelData { bounds: entry.0, vertex: entry.2, normal: entry.3 })
+      }
+    }
+
+    #[test]
+    fn corners_collapsed_onto_a_single_coarser_voxel_emit_nothing() {
+      // All 4 logical corners resolve onto the same `lg_size = 2` neighbor:
+      // this edge is entirely interior to a coarser voxel, whose own
+      // extraction pass already covers this patch of the seam.
+      let edge = T { low_corner: Point3::new(0, 1, 1), lg_size: 0, direction: Direction::X };
+      let neighbor_lg_size = [2, 2, 2, 2];
+
+      let mut voxels = Fixture::new();
+      voxels.put(voxel_data::bounds::new(0, 1, 1, 0), false, Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 0.0));
+      voxels.put(voxel_data::bounds::new(1, 1, 1, 0), true, Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 0.0));
+      voxels.put(voxel_data::bounds::new(0, 0, 0, 2), true, Point3::new(9.0, 9.0, 9.0), Vector3::new(0.0, 0.0, 1.0));
+
+      let mut polys: Vec<polygon::T<TestMaterial>> = Vec::new();
+      extract_lod(&mut voxels, &edge, neighbor_lg_size, |p| polys.push(p)).unwrap();
+
+      assert_eq!(polys.len(), 0);
+    }
+
+    #[test]
+    fn two_distinct_neighbors_straddling_the_quad_bridge_the_seam() {
+      // The case `corners < 3` used to drop silently: two corners resolve
+      // to one coarser neighbor, the other two resolve to a second, and
+      // neither neighbor's own extraction pass covers the interior seam
+      // between them, so `extract_lod` must bridge it itself.
+      let edge = T { low_corner: Point3::new(0, 0, 1), lg_size: 0, direction: Direction::X };
+      let neighbor_lg_size = [1, 1, 1, 1];
+
+      let a = voxel_data::bounds::new(0, 0, 0, 1);
+      let b = voxel_data::bounds::new(0, -1, 0, 1);
+      let v_a = Point3::new(2.0, 0.0, 0.0);
+      let n_a = Vector3::new(1.0, 0.0, 0.0);
+      let v_b = Point3::new(-2.0, 0.0, 0.0);
+      let n_b = Vector3::new(-1.0, 0.0, 0.0);
+
+      let mut voxels = Fixture::new();
+      voxels.put(voxel_data::bounds::new(0, 0, 1, 0), false, Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 0.0));
+      voxels.put(voxel_data::bounds::new(1, 0, 1, 0), true, Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 0.0));
+      voxels.put(a, true, v_a, n_a);
+      voxels.put(b, true, v_b, n_b);
+
+      let mut polys: Vec<polygon::T<TestMaterial>> = Vec::new();
+      extract_lod(&mut voxels, &edge, neighbor_lg_size, |p| polys.push(p)).unwrap();
+
+      assert_eq!(polys.len(), 4);
+      let center = Point3::new(0.0, 0.0, 0.0);
+      // The two repeated-corner legs of the fan are zero-area...
+      assert_eq!(polys[0].vertices, [v_a, v_a, center]);
+      assert_eq!(polys[2].vertices, [v_b, v_b, center]);
+      // ...and the other two are the bridging triangles that actually close
+      // the seam between the two neighbors.
+      assert_eq!(polys[1].vertices, [v_a, v_b, center]);
+      assert_eq!(polys[3].vertices, [v_b, v_a, center]);
+      for p in &polys {
+        assert_eq!(p.material, TestMaterial(true));
+      }
+    }
+
+    #[test]
+    fn three_distinct_neighbors_fan_as_a_single_triangle() {
+      // One pair of adjacent corners shares a coarser neighbor; the other
+      // two stay at `edge.lg_size`, so exactly 3 distinct voxels resolve
+      // and `extract_lod` emits them as one triangle, skipping the fan.
+      let edge = T { low_corner: Point3::new(0, 0, 1), lg_size: 0, direction: Direction::X };
+      let neighbor_lg_size = [1, 1, 0, 0];
+
+      let a = voxel_data::bounds::new(0, 0, 0, 1);
+      let b = voxel_data::bounds::new(0, -1, 0, 0);
+      let c = voxel_data::bounds::new(0, -1, 1, 0);
+      let v_a = Point3::new(1.0, 1.0, 1.0);
+      let n_a = Vector3::new(0.0, 0.0, 1.0);
+      let v_b = Point3::new(2.0, 2.0, 2.0);
+      let n_b = Vector3::new(0.0, 1.0, 0.0);
+      let v_c = Point3::new(3.0, 3.0, 3.0);
+      let n_c = Vector3::new(1.0, 0.0, 0.0);
+
+      let mut voxels = Fixture::new();
+      voxels.put(voxel_data::bounds::new(0, 0, 1, 0), false, Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 0.0));
+      voxels.put(voxel_data::bounds::new(1, 0, 1, 0), true, Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 0.0));
+      voxels.put(a, true, v_a, n_a);
+      voxels.put(b, true, v_b, n_b);
+      voxels.put(c, true, v_c, n_c);
+
+      let mut polys: Vec<polygon::T<TestMaterial>> = Vec::new();
+      extract_lod(&mut voxels, &edge, neighbor_lg_size, |p| polys.push(p)).unwrap();
+
+      assert_eq!(polys.len(), 1);
+      assert_eq!(polys[0].vertices, [v_a, v_b, v_c]);
+      assert_eq!(polys[0].normals, [n_a, n_b, n_c]);
+      assert_eq!(polys[0].material, TestMaterial(true));
+    }
+
+    #[test]
+    fn four_distinct_neighbors_fan_around_their_centroid() {
+      // No neighbor is coarser than `edge.lg_size`, so all 4 corners stay
+      // distinct; this should behave exactly like the uniform-resolution
+      // quad fan in `extract`.
+      let edge = T { low_corner: Point3::new(0, 0, 0), lg_size: 0, direction: Direction::X };
+      let neighbor_lg_size = [0, 0, 0, 0];
+
+      let v_a = Point3::new(4.0, 0.0, 0.0);
+      let n_a = Vector3::new(1.0, 0.0, 0.0);
+      let v_b = Point3::new(0.0, 4.0, 0.0);
+      let n_b = Vector3::new(0.0, 1.0, 0.0);
+      let v_c = Point3::new(-4.0, 0.0, 0.0);
+      let n_c = Vector3::new(-1.0, 0.0, 0.0);
+      let v_d = Point3::new(0.0, -4.0, 0.0);
+      let n_d = Vector3::new(0.0, -1.0, 0.0);
+
+      let mut voxels = Fixture::new();
+      // `edge.low_corner` is also logical corner 0, so its material (for
+      // `crossing`) and voxel data (for `resolve_voxels`) share one entry.
+      voxels.put(voxel_data::bounds::new(0, 0, 0, 0), false, v_a, n_a);
+      voxels.put(voxel_data::bounds::new(1, 0, 0, 0), true, Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 0.0));
+      voxels.put(voxel_data::bounds::new(0, 0, -1, 0), true, v_b, n_b);
+      voxels.put(voxel_data::bounds::new(0, -1, -1, 0), true, v_c, n_c);
+      voxels.put(voxel_data::bounds::new(0, -1, 0, 0), true, v_d, n_d);
+
+      let mut polys: Vec<polygon::T<TestMaterial>> = Vec::new();
+      extract_lod(&mut voxels, &edge, neighbor_lg_size, |p| polys.push(p)).unwrap();
+
+      let center = Point3::new(0.0, 0.0, 0.0);
+      assert_eq!(polys.len(), 4);
+      assert_eq!(polys[0].vertices, [v_a, v_b, center]);
+      assert_eq!(polys[1].vertices, [v_b, v_c, center]);
+      assert_eq!(polys[2].vertices, [v_c, v_d, center]);
+      assert_eq!(polys[3].vertices, [v_d, v_a, center]);
+      // The 4 normals cancel out exactly, so the fan center falls back to
+      // the zero normal rather than a meaningless unit vector.
+      assert_eq!(polys[0].normals[2], Vector3::zero());
+    }
+
+    #[test]
+    fn adjacent_edges_sharing_a_voxel_weld_to_one_vertex() {
+      // Two parallel X edges one unit apart in Z share 2 of their 4
+      // neighbor voxels (the quad corners between them); `extract_mesh`'s
+      // welding should map both edges' triangles onto the same vertex
+      // index for each shared voxel instead of duplicating it.
+      let edge_near = T { low_corner: Point3::new(0, 0, 0), lg_size: 0, direction: Direction::X };
+      let edge_far = T { low_corner: Point3::new(0, 0, 1), lg_size: 0, direction: Direction::X };
+
+      let mut voxels = Fixture::new();
+      voxels.put(voxel_data::bounds::new(0, 0, 0, 0), false, Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+      voxels.put(voxel_data::bounds::new(1, 0, 0, 0), true, Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 0.0));
+      voxels.put(voxel_data::bounds::new(0, 0, -1, 0), true, Point3::new(1.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+      voxels.put(voxel_data::bounds::new(0, -1, -1, 0), true, Point3::new(2.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+      voxels.put(voxel_data::bounds::new(0, -1, 0, 0), true, Point3::new(3.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+      voxels.put(voxel_data::bounds::new(0, 0, 1, 0), false, Point3::new(4.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+      voxels.put(voxel_data::bounds::new(1, 0, 1, 0), true, Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 0.0));
+      voxels.put(voxel_data::bounds::new(0, -1, 1, 0), true, Point3::new(5.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+
+      let mut mesh = mesh::T::new();
+      extract_mesh(&mut voxels, &edge_near, &mut mesh).unwrap();
+      extract_mesh(&mut voxels, &edge_far, &mut mesh).unwrap();
+
+      // 6 distinct voxels across the two edges (edge_near's 2 far corners
+      // are edge_far's 2 near corners), plus one non-deduped fan-center
+      // vertex per edge.
+      assert_eq!(mesh.positions.len(), 8);
+      assert_eq!(mesh.triangles.len(), 8);
+
+      let shared = voxel_data::bounds::new(0, 0, 0, 0);
+      let positions_before = mesh.positions.len();
+      let index = mesh.push_voxel_vertex(shared, Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+      assert_eq!(mesh.positions.len(), positions_before, "re-querying a seen voxel must not push a new vertex");
+      assert!(
+        mesh.triangles.iter().any(|t| t.contains(&index)),
+        "the shared voxel's welded index should appear in the mesh built from both edges",
+      );
+    }
+  }
+}
+
+/// Seam-free extraction across chunk boundaries.
+///
+/// When a grid is meshed chunk-by-chunk, `edge::neighbors` reaches into
+/// voxels that belong to adjacent chunks, and if those aren't available
+/// the edge silently errors out, producing cracks between chunks. `owns`
+/// deterministically picks exactly one of the two chunks sharing a
+/// boundary edge to emit its geometry, and `Margin` extends a chunk's
+/// `voxel_storage::T` with a one-voxel margin backed by its neighbors'
+/// storage, so `edge::extract`/`edge::extract_mesh` can still resolve
+/// voxels just across the boundary.
+pub mod chunk {
+  use cgmath::{Point3, Vector3};
+  use std::collections::HashMap;
+  use voxel_data;
+
+  use super::{edge, material, voxel_storage};
+
+  /// Does the chunk at `chunk_coordinate` (in units of `1 << chunk_lg_size`
+  /// voxels) own `edge`? Both chunks sharing a boundary edge compute the
+  /// same answer from the edge's low corner, so exactly one of them emits
+  /// the edge's geometry and the two chunks' meshes weld at the seam
+  /// instead of each producing their own slightly-different boundary
+  /// polygons.
+  ///
+  /// `edge.low_corner` is a coordinate at `edge.lg_size`, which may be
+  /// coarser than the chunk's own base resolution (e.g. an LOD transition
+  /// edge); assumes `chunk_lg_size >= edge.lg_size`, i.e. a chunk is never
+  /// smaller than an edge inside it.
+  pub fn owns(edge: &edge::T, chunk_lg_size: i16, chunk_coordinate: Point3<i32>) -> bool {
+    debug_assert!(chunk_lg_size >= edge.lg_size);
+    let shift = chunk_lg_size - edge.lg_size;
+    let divide = |n: i32| n >> shift;
+    chunk_coordinate ==
+      Point3::new(
+        divide(edge.low_corner.x),
+        divide(edge.low_corner.y),
+        divide(edge.low_corner.z),
+      )
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use super::*;
+
+    fn edge_at(x: i32, lg_size: i16) -> edge::T {
+      edge::T { low_corner: Point3::new(x, 0, 0), lg_size: lg_size, direction: edge::Direction::X }
+    }
+
+    #[test]
+    fn owns_shifts_by_the_difference_between_chunk_and_edge_lg_size() {
+      // A chunk is `1 << chunk_lg_size` base-resolution voxels wide, i.e.
+      // `1 << (chunk_lg_size - edge.lg_size)` voxels at `edge.lg_size`, so
+      // the shift must be `chunk_lg_size - edge.lg_size`, not
+      // `chunk_lg_size` itself: an edge coarser than the chunk's base
+      // resolution should still only move one chunk over for every
+      // `1 << (chunk_lg_size - edge.lg_size)` units of its own coordinate.
+      let chunk_lg_size = 3;
+      let edge_lg_size = 1;
+
+      // x=3 is the last unit still inside chunk 0 at this lg_size gap;
+      // x=4 is the first unit of chunk 1.
+      assert!(owns(&edge_at(3, edge_lg_size), chunk_lg_size, Point3::new(0, 0, 0)));
+      assert!(!owns(&edge_at(3, edge_lg_size), chunk_lg_size, Point3::new(1, 0, 0)));
+      assert!(owns(&edge_at(4, edge_lg_size), chunk_lg_size, Point3::new(1, 0, 0)));
+      assert!(!owns(&edge_at(4, edge_lg_size), chunk_lg_size, Point3::new(0, 0, 0)));
+    }
+
+    #[test]
+    fn owns_floors_toward_negative_infinity() {
+      // Negative coordinates must divide the same way floating-point
+      // voxel positions do (floor, not truncate), or a chunk boundary
+      // straddling zero would double-own or drop an edge.
+      let chunk_lg_size = 2;
+      let edge_lg_size = 0;
+      assert!(owns(&edge_at(-1, edge_lg_size), chunk_lg_size, Point3::new(-1, 0, 0)));
+      assert!(!owns(&edge_at(-1, edge_lg_size), chunk_lg_size, Point3::new(0, 0, 0)));
+    }
+  }
+
+  /// A chunk's `voxel_storage::T`, extended with a one-voxel margin backed
+  /// by its neighboring chunks, so a chunk can be extracted together with
+  /// a copy of its neighbors' border layer instead of erroring out at the
+  /// boundary.
+  ///
+  /// `classify` maps a queried voxel to its chunk's offset (in chunk-grid
+  /// coordinates) from this chunk: `(0, 0, 0)` for a voxel inside `here`,
+  /// or e.g. `(1, 0, 0)` for a voxel one chunk over on the +x side.
+  pub struct Margin<Voxels, Classify> {
+    #[allow(missing_docs)]
+    pub here: Voxels,
+    /// Neighboring chunks' storage, keyed by their offset from this chunk.
+    pub neighbors: HashMap<Vector3<i32>, Voxels>,
+    #[allow(missing_docs)]
+    pub classify: Classify,
+  }
+
+  impl<Voxels, Classify> Margin<Voxels, Classify> where
+    Classify: Fn(&voxel_data::bounds::T) -> Vector3<i32>,
+  {
+    fn storage_for(&mut self, voxel: &voxel_data::bounds::T) -> Option<&mut Voxels> {
+      match (self.classify)(voxel) {
+        Vector3 { x: 0, y: 0, z: 0 } => Some(&mut self.here),
+        offset => self.neighbors.get_mut(&offset),
+      }
+    }
+  }
+
+  impl<Material, Voxels, Classify> voxel_storage::T<Material> for Margin<Voxels, Classify> where
+    Material: material::T,
+    Voxels: voxel_storage::T<Material>,
+    Classify: Fn(&voxel_data::bounds::T) -> Vector3<i32>,
+  {
+    fn get_material(&mut self, voxel: &voxel_data::bounds::T) -> Option<Material> {
+      self.storage_for(voxel).and_then(|storage| storage.get_material(voxel))
+    }
+
+    fn get_voxel_data(&mut self, voxel: &voxel_data::bounds::T) -> Option<voxel_storage::VoxelData> {
+      self.storage_for(voxel).and_then(|storage| storage.get_voxel_data(voxel))
+    }
+
+    fn get_hermite_data(&mut self, voxel: &voxel_data::bounds::T) -> Option<voxel_storage::HermiteData> {
+      self.storage_for(voxel).and_then(|storage| storage.get_hermite_data(voxel))
+    }
+  }
+}
+
+/// Boolean combination of material volumes, evaluated lazily.
+///
+/// `csg::T` is a `voxel_storage::T` that composites two underlying
+/// volumes `a` and `b` with classic polygon boolean semantics: union
+/// keeps a point occupied if either operand occupies it, intersection
+/// keeps it only if both do, and difference removes `b`'s occupied
+/// region from `a`. This lets callers carve and combine voxel shapes and
+/// run `dual_contouring::edge::extract` over the composite without ever
+/// materializing a merged grid.
+pub mod csg {
+  use voxel_data;
+
+  use super::{material, voxel_storage};
+
+  /// Which boolean operation to apply to the two operand volumes.
+  pub enum Op<Material> {
+    /// Occupied wherever `a` or `b` is occupied.
+    Union,
+    /// Occupied only where both `a` and `b` are occupied.
+    Intersection,
+    /// Occupied where `a` is occupied and `b` isn't. `empty` is the
+    /// material reported (and meshed) on the carved-away interior, since
+    /// `csg::T` has no way to invent a new `Material` value of its own;
+    /// supply whatever this storage's non-opaque "air" material is.
+    Difference {
+      #[allow(missing_docs)]
+      empty: Material,
+    },
+  }
+
+  /// The boolean combination of voxel storages `a` and `b` under `op`.
+  pub struct T<Material, A, B> {
+    #[allow(missing_docs)]
+    pub a: A,
+    #[allow(missing_docs)]
+    pub b: B,
+    #[allow(missing_docs)]
+    pub op: Op<Material>,
+  }
+
+  impl<Material, A, B> T<Material, A, B> where Material: material::T + Clone {
+    fn combine_material(&self, a: Material, b: Material) -> Material {
+      let a_occupied = a.is_opaque();
+      let b_occupied = b.is_opaque();
+      match self.op {
+        Op::Union =>
+          if a_occupied { a } else if b_occupied { b } else { a },
+        Op::Intersection =>
+          if a_occupied && b_occupied { a } else if a_occupied { b } else { a },
+        Op::Difference { ref empty } =>
+          if a_occupied && b_occupied { empty.clone() } else { a },
+      }
+    }
+  }
+
+  #[cfg(test)]
+  mod tests {
+    use cgmath::{Point3, Vector3};
+
+    use super::*;
+    use super::super::qef;
+
+    #[derive(Clone, PartialEq, Eq, Debug)]
+    struct TestMaterial(bool);
+
+    impl material::T for TestMaterial {
+      fn is_opaque(&self) -> bool { self.0 }
+    }
+
+    fn combine(op: Op<TestMaterial>, a: bool, b: bool) -> bool {
+      let csg: T<TestMaterial, (), ()> = T { a: (), b: (), op: op };
+      csg.combine_material(TestMaterial(a), TestMaterial(b)).is_opaque()
+    }
+
+    #[test]
+    fn union_is_occupied_if_either_operand_is() {
+      assert!(combine(Op::Union, true, false));
+      assert!(combine(Op::Union, false, true));
+      assert!(combine(Op::Union, true, true));
+      assert!(!combine(Op::Union, false, false));
+    }
+
+    #[test]
+    fn intersection_is_occupied_only_if_both_operands_are() {
+      assert!(combine(Op::Intersection, true, true));
+      assert!(!combine(Op::Intersection, true, false));
+      assert!(!combine(Op::Intersection, false, true));
+      assert!(!combine(Op::Intersection, false, false));
+    }
+
+    #[test]
+    fn difference_carves_b_out_of_a() {
+      let empty = || Op::Difference { empty: TestMaterial(false) };
+      assert!(!combine(empty(), true, true));
+      assert!(combine(empty(), true, false));
+      assert!(!combine(empty(), false, true));
+      assert!(!combine(empty(), false, false));
+    }
+
+    /// A uniform operand: every voxel has the same material, vertex and
+    /// normal, so a test can tell `a`'s surface from `b`'s by which vertex
+    /// comes back.
+    struct Solid {
+      material: TestMaterial,
+      vertex: Point3<f32>,
+      normal: Vector3<f32>,
+    }
+
+    impl voxel_storage::T<TestMaterial> for Solid {
+      fn get_material(&mut self, _voxel: &voxel_data::bounds::T) -> Option<TestMaterial> {
+        Some(self.material.clone())
+      }
+
+      fn get_voxel_data(&mut self, voxel: &voxel_data::bounds::T) -> Option<voxel_storage::VoxelData> {
+        Some(voxel_storage::VoxelData { bounds: *voxel, vertex: self.vertex, normal: self.normal })
+      }
+
+      fn get_hermite_data(&mut self, _voxel: &voxel_data::bounds::T) -> Option<voxel_storage::HermiteData> {
+        Some(voxel_storage::HermiteData {
+          samples: vec![qef::Sample { position: self.vertex, normal: self.normal }],
+          low: Point3::new(0.0, 0.0, 0.0),
+          high: Point3::new(1.0, 1.0, 1.0),
+        })
+      }
+    }
+
+    fn solid(occupied: bool, vertex: Point3<f32>, normal: Vector3<f32>) -> Solid {
+      Solid { material: TestMaterial(occupied), vertex: vertex, normal: normal }
+    }
+
+    fn a_vertex() -> Point3<f32> { Point3::new(1.0, 0.0, 0.0) }
+    fn b_vertex() -> Point3<f32> { Point3::new(2.0, 0.0, 0.0) }
+    fn some_bounds() -> voxel_data::bounds::T { voxel_data::bounds::new(0, 0, 0, 0) }
+
+    #[test]
+    fn union_voxel_data_picks_the_occupied_operand() {
+      let mut csg = T {
+        a: solid(true, a_vertex(), Vector3::new(1.0, 0.0, 0.0)),
+        b: solid(false, b_vertex(), Vector3::new(0.0, 1.0, 0.0)),
+        op: Op::Union,
+      };
+      assert_eq!(csg.get_voxel_data(&some_bounds()).unwrap().vertex, a_vertex());
+
+      let mut csg = T {
+        a: solid(false, a_vertex(), Vector3::new(1.0, 0.0, 0.0)),
+        b: solid(true, b_vertex(), Vector3::new(0.0, 1.0, 0.0)),
+        op: Op::Union,
+      };
+      assert_eq!(csg.get_voxel_data(&some_bounds()).unwrap().vertex, b_vertex());
+    }
+
+    #[test]
+    fn intersection_voxel_data_surfaces_the_boundary_operand() {
+      // Both occupied: the surviving surface is `a`'s.
+      let mut csg = T {
+        a: solid(true, a_vertex(), Vector3::new(1.0, 0.0, 0.0)),
+        b: solid(true, b_vertex(), Vector3::new(0.0, 1.0, 0.0)),
+        op: Op::Intersection,
+      };
+      assert_eq!(csg.get_voxel_data(&some_bounds()).unwrap().vertex, a_vertex());
+
+      // Only `a` occupied: this voxel is outside the intersection, so the
+      // boundary surface here is `b`'s, not `a`'s.
+      let mut csg = T {
+        a: solid(true, a_vertex(), Vector3::new(1.0, 0.0, 0.0)),
+        b: solid(false, b_vertex(), Vector3::new(0.0, 1.0, 0.0)),
+        op: Op::Intersection,
+      };
+      assert_eq!(csg.get_voxel_data(&some_bounds()).unwrap().vertex, b_vertex());
+    }
+
+    #[test]
+    fn difference_voxel_data_flips_the_cut_boundary_normal() {
+      // Both occupied: this is the cut boundary, so `b`'s surface is used
+      // with its normal flipped to face out of the remaining solid (`a`).
+      let mut csg = T {
+        a: solid(true, a_vertex(), Vector3::new(1.0, 0.0, 0.0)),
+        b: solid(true, b_vertex(), Vector3::new(0.0, 1.0, 0.0)),
+        op: Op::Difference { empty: TestMaterial(false) },
+      };
+      let data = csg.get_voxel_data(&some_bounds()).unwrap();
+      assert_eq!(data.vertex, b_vertex());
+      assert_eq!(data.normal, Vector3::new(0.0, -1.0, 0.0));
+
+      // `a` occupied and not carved away here: `a`'s own surface, normal
+      // untouched.
+      let mut csg = T {
+        a: solid(true, a_vertex(), Vector3::new(1.0, 0.0, 0.0)),
+        b: solid(false, b_vertex(), Vector3::new(0.0, 1.0, 0.0)),
+        op: Op::Difference { empty: TestMaterial(false) },
+      };
+      let data = csg.get_voxel_data(&some_bounds()).unwrap();
+      assert_eq!(data.vertex, a_vertex());
+      assert_eq!(data.normal, Vector3::new(1.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn hermite_data_flips_the_same_way_voxel_data_does() {
+      let mut csg = T {
+        a: solid(true, a_vertex(), Vector3::new(1.0, 0.0, 0.0)),
+        b: solid(true, b_vertex(), Vector3::new(0.0, 1.0, 0.0)),
+        op: Op::Difference { empty: TestMaterial(false) },
+      };
+      let data = csg.get_hermite_data(&some_bounds()).unwrap();
+      assert_eq!(data.samples[0].position, b_vertex());
+      assert_eq!(data.samples[0].normal, Vector3::new(0.0, -1.0, 0.0));
+    }
+  }
+
+  impl<Material, A, B> voxel_storage::T<Material> for T<Material, A, B> where
+    Material: material::T + Clone,
+    A: voxel_storage::T<Material>,
+    B: voxel_storage::T<Material>,
+  {
+    fn get_material(&mut self, voxel: &voxel_data::bounds::T) -> Option<Material> {
+      let a = match self.a.get_material(voxel) { None => return None, Some(m) => m };
+      let b = match self.b.get_material(voxel) { None => return None, Some(m) => m };
+      Some(self.combine_material(a, b))
+    }
+
+    fn get_voxel_data(&mut self, voxel: &voxel_data::bounds::T) -> Option<voxel_storage::VoxelData> {
+      let a_material = match self.a.get_material(voxel) { None => return None, Some(m) => m };
+      let b_material = match self.b.get_material(voxel) { None => return None, Some(m) => m };
+      let a_occupied = a_material.is_opaque();
+      let b_occupied = b_material.is_opaque();
+
+      match self.op {
+        Op::Union =>
+          if a_occupied { self.a.get_voxel_data(voxel) } else { self.b.get_voxel_data(voxel) },
+        Op::Intersection =>
+          if a_occupied && b_occupied { self.a.get_voxel_data(voxel) }
+          else if a_occupied { self.b.get_voxel_data(voxel) }
+          else { self.a.get_voxel_data(voxel) },
+        Op::Difference { .. } =>
+          if a_occupied && !b_occupied {
+            self.a.get_voxel_data(voxel)
+          } else if a_occupied && b_occupied {
+            // The cut boundary: `a`'s surface here was carved away by
+            // `b`, so the new visible surface is `b`'s, flipped to face
+            // outward from the solid that remains (`a`) instead of
+            // outward from `b`.
+            self.b.get_voxel_data(voxel).map(|mut data| {
+              data.normal = -data.normal;
+              data
+            })
+          } else {
+            self.a.get_voxel_data(voxel)
+          },
+      }
+    }
+
+    fn get_hermite_data(&mut self, voxel: &voxel_data::bounds::T) -> Option<voxel_storage::HermiteData> {
+      let a_material = match self.a.get_material(voxel) { None => return None, Some(m) => m };
+      let b_material = match self.b.get_material(voxel) { None => return None, Some(m) => m };
+      let a_occupied = a_material.is_opaque();
+      let b_occupied = b_material.is_opaque();
+
+      match self.op {
+        Op::Union =>
+          if a_occupied { self.a.get_hermite_data(voxel) } else { self.b.get_hermite_data(voxel) },
+        Op::Intersection =>
+          if a_occupied && b_occupied { self.a.get_hermite_data(voxel) }
+          else if a_occupied { self.b.get_hermite_data(voxel) }
+          else { self.a.get_hermite_data(voxel) },
+        Op::Difference { .. } =>
+          if a_occupied && !b_occupied {
+            self.a.get_hermite_data(voxel)
+          } else if a_occupied && b_occupied {
+            // Same cut-boundary surface as `get_voxel_data`: `b`'s samples,
+            // with normals flipped to face outward from the solid that
+            // remains (`a`) instead of outward from `b`.
+            self.b.get_hermite_data(voxel).map(|mut data| {
+              for sample in &mut data.samples {
+                sample.normal = -sample.normal;
+              }
+              data
+            })
+          } else {
+            self.a.get_hermite_data(voxel)
+          },
+      }
+    }
+  }
 }